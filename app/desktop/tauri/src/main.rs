@@ -8,16 +8,79 @@ use tauri::{
     // CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem,
     Manager, WindowEvent,
 };
+use tauri_plugin_log::LogTarget;
+use fs2::FileExt;
 use tokio::process::Command;
 use tokio::io::{BufReader, AsyncBufReadExt};
 use std::process::Stdio;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::Duration;
 
 struct SidecarPid(Mutex<Option<u32>>);
+struct ShuttingDown(AtomicBool);
+/// Holding this open for the app's lifetime keeps the advisory lock taken in
+/// `acquire_single_instance_lock` held; the OS releases it automatically
+/// when this handle is dropped (including on an unclean exit/crash).
+struct InstanceLockFile(std::fs::File);
+
+/// Exponential backoff schedule for sidecar restarts: doubles from
+/// `INITIAL_BACKOFF_MS` up to `MAX_BACKOFF_MS`, giving up once `MAX_RETRIES`
+/// restarts in a row have also crashed so a permanently broken backend
+/// doesn't spin-loop.
+const INITIAL_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+const MAX_RETRIES: u32 = 8;
+
+/// A run that stays up at least this long is considered healthy: a crash
+/// after this point resets the retry streak instead of counting toward
+/// `MAX_RETRIES`, so sporadic crashes in an otherwise-fine backend don't
+/// eventually trip the same "persistently broken" guard as a backend that
+/// fails immediately on every restart.
+const HEALTHY_UPTIME_THRESHOLD: Duration = Duration::from_secs(60);
+
+/// Sentinel line prefix the sidecar uses to emit its structured readiness
+/// handshake, distinguishing it from ordinary human-readable log lines.
+const HANDSHAKE_SENTINEL: &str = "@@SAGE@@";
 
 #[derive(Clone, serde::Serialize)]
-struct Payload {
+struct ReadyPayload {
     port: u16,
+    pid: Option<u32>,
+    version: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum SidecarHandshake {
+    Ready {
+        port: u16,
+        pid: Option<u32>,
+        version: Option<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Clone, serde::Serialize)]
+struct RestartingPayload {
+    attempt: u32,
+    delay_ms: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct BackendFailedPayload {
+    reason: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct BackendLogPayload {
+    level: String,
+    line: String,
 }
 
 #[tauri::command]
@@ -25,7 +88,593 @@ fn get_server_port() -> Option<u16> {
     std::env::var("SAGE_PORT").ok().and_then(|p| p.parse().ok())
 }
 
+#[cfg(target_os = "linux")]
+struct DbusConnection(dbus::blocking::SyncConnection);
+
+/// Opens `path`'s parent directory in the file manager without attempting
+/// to highlight a specific item — used as a fallback when no D-Bus session
+/// connection is available or the D-Bus reveal call fails outright (e.g. no
+/// file manager implements `FileManager1`).
+#[cfg(target_os = "linux")]
+fn open_parent_dir(path: &str) -> Result<(), String> {
+    let parent = std::path::Path::new(path)
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("/"));
+
+    std::process::Command::new("xdg-open")
+        .arg(&parent)
+        .spawn()
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// Percent-encodes a filesystem path into a `file://` URI per RFC 3986,
+/// escaping everything outside the unreserved set (including spaces, `#`,
+/// `%`, and non-ASCII bytes) so the D-Bus `ShowItems` call always receives
+/// a well-formed URI regardless of what characters the path contains.
+#[cfg(target_os = "linux")]
+fn path_to_file_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(*byte as char);
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Reveals `path` in the Linux file manager via the freedesktop
+/// `org.freedesktop.FileManager1` D-Bus interface, falling back to just
+/// opening the parent directory if there is no D-Bus session connection or
+/// the D-Bus call itself fails (e.g. no running file manager implements the
+/// interface).
+#[cfg(target_os = "linux")]
+fn reveal_on_linux(app_handle: &tauri::AppHandle, path: &str) -> Result<(), String> {
+    let state = match app_handle.try_state::<DbusConnection>() {
+        Some(state) => state,
+        None => return open_parent_dir(path),
+    };
+
+    let uri = path_to_file_uri(path);
+    let proxy = dbus::blocking::Proxy::new(
+        "org.freedesktop.FileManager1",
+        "/org/freedesktop/FileManager1",
+        Duration::from_secs(5),
+        &state.0,
+    );
+    proxy
+        .method_call::<(), _, _, _>(
+            "org.freedesktop.FileManager1",
+            "ShowItems",
+            (vec![uri], String::new()),
+        )
+        .or_else(|_| open_parent_dir(path))
+}
+
+/// Reveals a workspace path (e.g. a generated skill or session output) in
+/// the OS file manager, highlighting it where the platform allows it.
+#[tauri::command]
+fn reveal_in_file_manager(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        reveal_on_linux(&app_handle, &path)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        std::process::Command::new("explorer")
+            .arg(format!("/select,{}", path))
+            .spawn()
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = &app_handle;
+        std::process::Command::new("open")
+            .args(["-R", &path])
+            .spawn()
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        let _ = (&app_handle, &path);
+        Err("revealing files is not supported on this platform".to_string())
+    }
+}
+
+fn sage_home_dir() -> PathBuf {
+    let home_dir = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_default();
+    PathBuf::from(home_dir).join(".sage")
+}
+
+fn instance_lock_path() -> PathBuf {
+    sage_home_dir().join("sage.lock")
+}
+
+/// Connects to a running instance's focus socket and forwards this
+/// process's CLI args so the other instance can surface itself.
+fn forward_to_running_instance(port: u16) -> bool {
+    use std::net::TcpStream;
+    match TcpStream::connect(("127.0.0.1", port)) {
+        Ok(mut stream) => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let _ = stream.write_all(args.join("\n").as_bytes());
+            let _ = stream.shutdown(std::net::Shutdown::Write);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// How many times (with a short pause between) we retry forwarding to a
+/// live instance before giving up. Covers the narrow window where the
+/// other instance holds the lock but its focus listener thread hasn't
+/// started accepting connections yet.
+const FORWARD_RETRIES: u32 = 5;
+const FORWARD_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Tries to become the single running instance of Sage by taking an
+/// exclusive OS-level advisory lock (`flock` on Unix, a locking handle on
+/// Windows) on `~/.sage/sage.lock`. This is atomic at the kernel level —
+/// unlike a plain read-then-write on the file's contents — and the lock is
+/// released automatically if we crash or exit, so it can never go stale the
+/// way a PID recorded in the file can (a dead PID can be reused by an
+/// unrelated process).
+///
+/// Returns the still-open lockfile (which must be kept alive for the app's
+/// lifetime to hold the lock) and the focus-listener socket if we acquired
+/// it, `Ok(None)` if another instance already holds the lock (whether or not
+/// forwarding our CLI args to it succeeded), or `Err` describing why the lock couldn't
+/// even be attempted (e.g. the lockfile's directory isn't writable). This
+/// runs before any Tauri/window context exists, so failures are reported by
+/// the caller via a parentless dialog rather than by panicking silently.
+fn acquire_single_instance_lock() -> Result<Option<(std::fs::File, TcpListener)>, String> {
+    let dir = sage_home_dir();
+    std::fs::create_dir_all(&dir).ok();
+    let lock_path = instance_lock_path();
+
+    let lock_file = std::fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|err| format!("Could not open the single-instance lockfile at {}: {}", lock_path.display(), err))?;
+
+    if lock_file.try_lock_exclusive().is_err() {
+        // Another process genuinely holds the lock right now.
+        let port = std::fs::read_to_string(&lock_path)
+            .ok()
+            .and_then(|contents| contents.split_whitespace().nth(1).map(str::to_string))
+            .and_then(|port| port.parse::<u16>().ok());
+
+        if let Some(port) = port {
+            for attempt in 1..=FORWARD_RETRIES {
+                if forward_to_running_instance(port) {
+                    println!("Another Sage instance is already running, focusing it");
+                    return Ok(None);
+                }
+                eprintln!("Could not reach the running instance yet, retrying ({}/{})", attempt, FORWARD_RETRIES);
+                std::thread::sleep(FORWARD_RETRY_DELAY);
+            }
+        }
+
+        println!("Another Sage instance is already running");
+        return Ok(None);
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|err| format!("Could not bind the single-instance focus socket: {}", err))?;
+    let port = listener.local_addr().unwrap().port();
+
+    use std::io::{Seek, SeekFrom};
+    let mut file = &lock_file;
+    file.set_len(0)
+        .and_then(|_| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| write!(file, "{} {}", std::process::id(), port))
+        .map_err(|err| format!("Could not write the single-instance lockfile: {}", err))?;
+
+    Ok(Some((lock_file, listener)))
+}
+
+/// Watches the single-instance lock socket on a background thread; every
+/// connection carries the relaunched process's forwarded CLI args
+/// (newline-separated, may be empty), which we log, then bring the main
+/// window to the front.
+fn spawn_instance_listener(listener: TcpListener, app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut forwarded = String::new();
+            use std::io::Read;
+            let _ = stream.read_to_string(&mut forwarded);
+            println!("Relaunch forwarded args: {:?}", forwarded.lines().collect::<Vec<_>>());
+
+            if let Some(window) = app_handle.get_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+    });
+}
+
+/// Binds an ephemeral TCP port and publishes it via `SAGE_PORT` so the
+/// Python backend can read it back out of its own environment.
+fn bind_fresh_port() -> std::io::Result<u16> {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    std::env::set_var("SAGE_PORT", port.to_string());
+    Ok(port)
+}
+
+/// Names tried on `PATH` when looking for a Python interpreter, in order of
+/// preference. Covers the Windows convention (`python`) and systems that
+/// only ship a versioned binary.
+const PYTHON_CANDIDATE_NAMES: &[&str] = &["python3", "python", "python3.12", "python3.11", "python3.10"];
+
+/// Resolves the Python interpreter used to run the backend in debug builds.
+/// Honors `SAGE_PYTHON` first, then a `.sage/venv` virtualenv, then falls
+/// back to searching `PATH` for known interpreter names — validating each
+/// candidate can actually import the top-level modules `entry_py` imports
+/// before selecting it.
+fn resolve_python_interpreter(entry_py: &std::path::Path) -> Result<String, String> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    if let Ok(explicit) = std::env::var("SAGE_PYTHON") {
+        candidates.push(PathBuf::from(explicit));
+    }
+
+    let venv_bin_dir = if cfg!(windows) { "Scripts" } else { "bin" };
+    let venv_python_name = if cfg!(windows) { "python.exe" } else { "python" };
+    candidates.push(sage_home_dir().join("venv").join(venv_bin_dir).join(venv_python_name));
+
+    for name in PYTHON_CANDIDATE_NAMES {
+        if let Ok(found) = which::which(name) {
+            candidates.push(found);
+        }
+    }
+
+    for candidate in &candidates {
+        if python_interpreter_is_usable(candidate, entry_py) {
+            let resolved = candidate.to_string_lossy().to_string();
+            println!("Resolved Python interpreter: {}", resolved);
+            return Ok(resolved);
+        }
+    }
+
+    Err("Could not find a Python interpreter able to import the Sage backend. Set SAGE_PYTHON to an explicit path, or install the backend's dependencies.".to_string())
+}
+
+/// Collects the top-level modules `entry_py` imports (`import x`, `from x
+/// import ...`), skipping relative imports, so we can check a candidate
+/// interpreter actually has them available rather than just existing.
+fn top_level_imports(source: &str) -> Vec<String> {
+    let mut modules = std::collections::BTreeSet::new();
+    for line in source.lines() {
+        let line = line.trim_start();
+        if let Some(rest) = line.strip_prefix("import ") {
+            // "import a, b as c" — every comma-separated name is a separate
+            // dependency, not just the first.
+            for name in rest.split(',') {
+                let name = name.trim();
+                let name = name.split_whitespace().next().unwrap_or(name);
+                if !name.is_empty() {
+                    modules.insert(name.split('.').next().unwrap_or(name).to_string());
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("from ") {
+            if let Some(name) = rest.split_whitespace().next().filter(|name| !name.starts_with('.')) {
+                modules.insert(name.split('.').next().unwrap_or(name).to_string());
+            }
+        }
+    }
+    modules.into_iter().collect()
+}
+
+/// Confirms a candidate is not just a working Python, but one that can
+/// actually import the top-level packages `entry_py` imports (fastapi,
+/// uvicorn, ...); `entry_py` itself is not imported — catching a stock Python that's missing the
+/// backend's dependencies before we try to spawn it for real.
+fn python_interpreter_is_usable(candidate: &std::path::Path, entry_py: &std::path::Path) -> bool {
+    if !candidate.exists() {
+        return false;
+    }
+
+    let source = match std::fs::read_to_string(entry_py) {
+        Ok(source) => source,
+        Err(_) => return false,
+    };
+
+    for module in top_level_imports(&source) {
+        let imported = std::process::Command::new(candidate)
+            .args(["-c", &format!("import {}", module)])
+            .output()
+            .map(|out| out.status.success())
+            .unwrap_or(false);
+        if !imported {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Resolves the command and arguments used to launch the Python backend,
+/// preferring a local `entry.py` in debug builds and falling back to the
+/// bundled sidecar binary otherwise.
+fn resolve_backend_command(app_handle: &tauri::AppHandle) -> Result<(String, Vec<String>), String> {
+    if cfg!(debug_assertions) {
+        // In debug mode, try to run python directly
+        // We need to find the python script path relative to the project root
+        // The current working directory when running `cargo tauri dev` is typically app/desktop/tauri
+        // So we need to go up to app/desktop/core/main.py or entry.py
+        // Let's assume we are in app/desktop/tauri
+        let mut script_path = std::env::current_dir().unwrap();
+        // If we are in tauri directory, we go up to find entry.py
+        if script_path.ends_with("tauri") {
+            script_path.pop(); // app/desktop
+        } else if script_path.ends_with("src-tauri") {
+            script_path.pop(); // app/desktop (if named src-tauri)
+        }
+
+        let entry_py = script_path.join("entry.py");
+
+        if entry_py.exists() {
+            let python = resolve_python_interpreter(&entry_py)?;
+            println!("Running python script directly: {:?}", entry_py);
+            return Ok((python, vec![entry_py.to_string_lossy().to_string()]));
+        }
+
+        println!("Python script not found at {:?}, falling back to sidecar", script_path);
+    }
+
+    let sidecar_dir = app_handle
+        .path_resolver()
+        .resolve_resource("sidecar")
+        .ok_or_else(|| "failed to resolve the bundled sidecar resource".to_string())?;
+
+    let sidecar_executable = if cfg!(target_os = "windows") {
+        sidecar_dir.join("sage-desktop.exe")
+    } else {
+        sidecar_dir.join("sage-desktop")
+    };
+    Ok((sidecar_executable.to_string_lossy().to_string(), vec![]))
+}
+
+/// Shows a native blocking message box describing a fatal startup failure
+/// so the user sees an actionable error instead of the app silently failing
+/// to open. It does not exit the app; callers do that afterwards.
+fn show_fatal_dialog(app_handle: &tauri::AppHandle, title: &str, message: &str) {
+    eprintln!("{}: {}", title, message);
+    let window = app_handle.get_window("main");
+    tauri::api::dialog::blocking::message(window.as_ref(), title, message);
+}
+
+/// Same as `show_fatal_dialog`, but for failures that happen before a
+/// `tauri::AppHandle` exists yet (e.g. acquiring the single-instance lock in
+/// `main` before `tauri::Builder` has run).
+fn show_fatal_dialog_without_window(title: &str, message: &str) {
+    eprintln!("{}: {}", title, message);
+    tauri::api::dialog::blocking::message(None::<&tauri::Window>, title, message);
+}
+
+/// Number of trailing stderr lines kept around so a crash dialog can show
+/// the user what the backend actually printed before it died.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Picks out the Python log level (INFO/WARNING/ERROR/DEBUG) prefixing a
+/// sidecar line, defaulting to "info" for lines that don't carry one (e.g.
+/// stray prints) so backend severity survives the trip into our own logs.
+fn parse_python_log_level(line: &str) -> &'static str {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("ERROR") || trimmed.starts_with("CRITICAL") {
+        "error"
+    } else if trimmed.starts_with("WARNING") || trimmed.starts_with("WARN") {
+        "warn"
+    } else if trimmed.starts_with("DEBUG") {
+        "debug"
+    } else {
+        "info"
+    }
+}
+
+/// Routes a single line of sidecar output (stdout or stderr) through the
+/// `log` crate at the level the backend reported, and forwards it to the
+/// webview so the frontend can render a live log panel.
+fn handle_sidecar_line(app_handle: &tauri::AppHandle, line: &str) {
+    let level = parse_python_log_level(line);
+    match level {
+        "error" => log::error!(target: "sage::backend", "{}", line),
+        "warn" => log::warn!(target: "sage::backend", "{}", line),
+        "debug" => log::debug!(target: "sage::backend", "{}", line),
+        _ => log::info!(target: "sage::backend", "{}", line),
+    }
+    app_handle
+        .emit_all(
+            "sage-backend-log",
+            BackendLogPayload { level: level.to_string(), line: line.to_string() },
+        )
+        .ok();
+}
+
+/// Spawns the backend once, streams its stdout looking for the ready line,
+/// and waits for it to exit. Returns `Ok((status, stderr_tail))` once the
+/// child exits, or `Err(description)` if the backend could not be launched
+/// at all (missing interpreter, unresolvable sidecar resource, etc) or could
+/// not be waited on.
+async fn spawn_backend_once(app_handle: &tauri::AppHandle, port: u16) -> Result<(std::process::ExitStatus, String), String> {
+    let (command, args) = resolve_backend_command(app_handle)?;
+
+    println!("Spawning backend: {} {:?}", command, args);
+
+    let mut child = Command::new(&command)
+        .args(&args)
+        .env("SAGE_PORT", port.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Could not start the Sage backend ({}): {}", command, err))?;
+
+    if let Some(id) = child.id() {
+        let state = app_handle.state::<SidecarPid>();
+        *state.0.lock().unwrap() = Some(id);
+    }
+
+    println!("Python sidecar spawned");
+
+    let stderr = child.stderr.take().expect("Failed to capture stderr");
+    let stderr_tail = std::sync::Arc::new(Mutex::new(std::collections::VecDeque::with_capacity(STDERR_TAIL_LINES)));
+    let stderr_tail_writer = stderr_tail.clone();
+    let stderr_app_handle = app_handle.clone();
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            handle_sidecar_line(&stderr_app_handle, &line);
+            let mut tail = stderr_tail_writer.lock().unwrap();
+            if tail.len() == STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    });
+
+    let stdout = child.stdout.take().expect("Failed to capture stdout");
+    let mut reader = BufReader::new(stdout).lines();
+
+    // Read events from sidecar
+    while let Ok(Some(line)) = reader.next_line().await {
+        let line: String = line;
+        match line.strip_prefix(HANDSHAKE_SENTINEL) {
+            Some(payload) => match serde_json::from_str::<SidecarHandshake>(payload) {
+                Ok(SidecarHandshake::Ready { port, pid, version }) => {
+                    println!("Backend ready on port {} (pid={:?}, version={:?})", port, pid, version);
+                    app_handle
+                        .emit_all("sage-desktop-ready", ReadyPayload { port, pid, version })
+                        .ok();
+                }
+                Ok(SidecarHandshake::Error { message }) => {
+                    app_handle
+                        .emit_all("sage-backend-failed", BackendFailedPayload { reason: message })
+                        .ok();
+                }
+                Err(err) => {
+                    eprintln!("Malformed sidecar handshake line {:?}: {}", payload, err);
+                }
+            },
+            None => handle_sidecar_line(app_handle, &line),
+        }
+    }
+
+    // Wait for child to exit
+    let status = child
+        .wait()
+        .await
+        .map_err(|err| format!("backend process could not be waited on: {}", err))?;
+    println!("Sidecar exited with status: {:?}", status);
+    let tail = stderr_tail.lock().unwrap().iter().cloned().collect::<Vec<_>>().join("\n");
+    Ok((status, tail))
+}
+
+/// Supervises the backend for the lifetime of the app: restarts it on an
+/// unclean exit with exponential backoff, rebinding a fresh port each time,
+/// until `shutting_down` is set or `MAX_RETRIES` restarts in a row have also
+/// crashed.
+async fn supervise_backend(app_handle: tauri::AppHandle) {
+    let mut backoff_ms = INITIAL_BACKOFF_MS;
+    let mut attempt: u32 = 0;
+
+    loop {
+        let run_started_at = std::time::Instant::now();
+        let port = match bind_fresh_port() {
+            Ok(port) => port,
+            Err(err) => {
+                let reason = format!("Could not find a free port for the Sage backend: {}", err);
+                app_handle
+                    .emit_all("sage-backend-failed", BackendFailedPayload { reason: reason.clone() })
+                    .ok();
+                show_fatal_dialog(&app_handle, "Sage failed to start", &reason);
+                app_handle.exit(1);
+                return;
+            }
+        };
+        println!("Set SAGE_PORT: {}", port);
+
+        let outcome = spawn_backend_once(&app_handle, port).await;
+
+        if app_handle.state::<ShuttingDown>().0.load(Ordering::SeqCst) {
+            // A clean shutdown was requested; don't restart.
+            return;
+        }
+
+        let (status, stderr_tail) = match outcome {
+            Ok(outcome) => outcome,
+            Err(reason) => {
+                // The backend couldn't even be launched (bad interpreter, missing
+                // resource, ...); retrying won't help, so surface it immediately.
+                app_handle
+                    .emit_all("sage-backend-failed", BackendFailedPayload { reason: reason.clone() })
+                    .ok();
+                show_fatal_dialog(&app_handle, "Sage failed to start", &reason);
+                app_handle.exit(1);
+                return;
+            }
+        };
+
+        if status.success() {
+            return;
+        }
+
+        if run_started_at.elapsed() >= HEALTHY_UPTIME_THRESHOLD {
+            // The backend ran long enough to be considered healthy before this
+            // crash, so start a new streak instead of adding it to the
+            // back-to-back crashes MAX_RETRIES is meant to catch.
+            attempt = 0;
+            backoff_ms = INITIAL_BACKOFF_MS;
+        }
+
+        attempt += 1;
+        if attempt > MAX_RETRIES {
+            let mut reason = format!("The Sage backend crashed {} times in a row and was not restarted again.", attempt);
+            if !stderr_tail.is_empty() {
+                reason.push_str("\n\n");
+                reason.push_str(&stderr_tail);
+            }
+            app_handle
+                .emit_all("sage-backend-failed", BackendFailedPayload { reason: reason.clone() })
+                .ok();
+            show_fatal_dialog(&app_handle, "Sage backend stopped responding", &reason);
+            app_handle.exit(1);
+            return;
+        }
+
+        println!("Backend exited unexpectedly, restarting in {}ms (attempt {})", backoff_ms, attempt);
+        app_handle
+            .emit_all(
+                "sage-backend-restarting",
+                RestartingPayload { attempt, delay_ms: backoff_ms },
+            )
+            .ok();
+
+        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+        backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
+    }
+}
+
 fn main() {
+    let (lock_file, instance_listener) = match acquire_single_instance_lock() {
+        Ok(Some(lock)) => lock,
+        Ok(None) => return,
+        Err(reason) => {
+            show_fatal_dialog_without_window("Sage failed to start", &reason);
+            std::process::exit(1);
+        }
+    };
+
     /*
     // Tray setup
     let quit = CustomMenuItem::new("quit".to_string(), "Quit");
@@ -39,16 +688,21 @@ fn main() {
 
     tauri::Builder::default()
         .manage(SidecarPid(Mutex::new(None)))
-        // .plugin(tauri_plugin_log::Builder::default().targets([
-        //     LogTarget::LogDir,
-        //     LogTarget::Stdout,
-        //     LogTarget::Webview,
-        // ]).build())
+        .manage(ShuttingDown(AtomicBool::new(false)))
+        .manage(InstanceLockFile(lock_file))
+        .plugin(tauri_plugin_log::Builder::default().targets([
+            LogTarget::LogDir,
+            LogTarget::Stdout,
+            LogTarget::Webview,
+        ]).build())
         .on_window_event(|event| match event.event() {
             WindowEvent::Destroyed => {
                 // When the main window is destroyed (closed), exit the app.
                 // Use app_handle.exit(0) to ensure proper cleanup of child processes.
                 let app_handle = event.window().app_handle();
+                if let Some(state) = app_handle.try_state::<ShuttingDown>() {
+                    state.0.store(true, Ordering::SeqCst);
+                }
                 if let Some(state) = app_handle.try_state::<SidecarPid>() {
                     let mut pid_guard = state.0.lock().unwrap();
                     if let Some(pid) = *pid_guard {
@@ -65,6 +719,8 @@ fn main() {
                         *pid_guard = None;
                     }
                 }
+                // The advisory lock in `InstanceLockFile` is released when the
+                // app process exits (its fd closes); no explicit unlock needed.
                 event.window().app_handle().exit(0);
             }
             _ => {}
@@ -94,10 +750,25 @@ fn main() {
             _ => {}
         })
         */
-        .setup(|app| {
+        .setup(move |app| {
             let _window = app.get_window("main").unwrap();
             let app_handle = app.handle();
-            
+
+            spawn_instance_listener(instance_listener, app_handle.clone());
+
+            #[cfg(target_os = "linux")]
+            {
+                match dbus::blocking::SyncConnection::new_session() {
+                    Ok(conn) => {
+                        app.manage(DbusConnection(conn));
+                    }
+                    Err(err) => eprintln!(
+                        "Could not open D-Bus session connection, \"reveal in file manager\" will fall back to xdg-open: {}",
+                        err
+                    ),
+                }
+            }
+
             // Set default environment variables
             std::env::set_var("SAGE_USE_SANDBOX", "False");
             // Get home directory from environment variable
@@ -110,109 +781,50 @@ fn main() {
             std::env::set_var("SAGE_WORKSPACE_PATH", &session_workspace);
             println!("Set SAGE_SKILL_WORKSPACE: {}", skill_workspace);
 
-            // Find a free port
-            let port = std::net::TcpListener::bind("127.0.0.1:0")
-                .map(|l| l.local_addr().unwrap().port())
-                .expect("failed to find free port");
-            std::env::set_var("SAGE_PORT", port.to_string());
-            println!("Set SAGE_PORT: {}", port);
-            
-            tauri::async_runtime::spawn(async move {
-                // Determine how to run the backend
-                let (command, args) = if cfg!(debug_assertions) {
-                    // In debug mode, try to run python directly
-                    // We need to find the python script path relative to the project root
-                    // The current working directory when running `cargo tauri dev` is typically app/desktop/tauri
-                    // So we need to go up to app/desktop/core/main.py or entry.py
-                    // Let's assume we are in app/desktop/tauri
-                    let mut script_path = std::env::current_dir().unwrap();
-                    // If we are in tauri directory, we go up to find entry.py
-                    if script_path.ends_with("tauri") {
-                        script_path.pop(); // app/desktop
-                    } else if script_path.ends_with("src-tauri") {
-                        script_path.pop(); // app/desktop (if named src-tauri)
-                    }
-                    
-                    let entry_py = script_path.join("entry.py");
-                    
-                    if entry_py.exists() {
-                        println!("Running python script directly: {:?}", entry_py);
-                        ("python3".to_string(), vec![entry_py.to_string_lossy().to_string()])
-                    } else {
-                        // Fallback to sidecar if script not found
-                         println!("Python script not found at {:?}, falling back to sidecar", script_path);
-                         // Resolve the sidecar path from resources
-                        let sidecar_dir = app_handle.path_resolver()
-                            .resolve_resource("sidecar")
-                            .expect("failed to resolve sidecar resource");
-                        
-                        let sidecar_executable = if cfg!(target_os = "windows") {
-                            sidecar_dir.join("sage-desktop.exe")
-                        } else {
-                            sidecar_dir.join("sage-desktop")
-                        };
-                        (sidecar_executable.to_string_lossy().to_string(), vec![])
-                    }
-                } else {
-                     // In release mode, always use sidecar
-                    let sidecar_dir = app_handle.path_resolver()
-                        .resolve_resource("sidecar")
-                        .expect("failed to resolve sidecar resource");
-                    
-                    let sidecar_executable = if cfg!(target_os = "windows") {
-                        sidecar_dir.join("sage-desktop.exe")
-                    } else {
-                        sidecar_dir.join("sage-desktop")
-                    };
-                    (sidecar_executable.to_string_lossy().to_string(), vec![])
-                };
-
-                println!("Spawning backend: {} {:?}", command, args);
-
-                let mut child = Command::new(command)
-                    .args(args)
-                    .env("SAGE_PORT", port.to_string())
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::inherit())
-                    .spawn()
-                    .expect("Failed to spawn backend");
-
-                if let Some(id) = child.id() {
-                    let state = app_handle.state::<SidecarPid>();
-                    *state.0.lock().unwrap() = Some(id);
-                }
-                
-                println!("Python sidecar spawned");
-                
-                let stdout = child.stdout.take().expect("Failed to capture stdout");
-                let mut reader = BufReader::new(stdout).lines();
-
-                // Read events from sidecar
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let line: String = line;
-                    println!("PYTHON: {}", line);
-                    if line.contains("Starting Sage Desktop Server on port") {
-                        // Extract port. Line format: "Starting Sage Desktop Server on port 12345..."
-                        if let Some(last_word) = line.split_whitespace().rev().next() {
-                            let clean_port: &str = last_word.trim_matches('.');
-                            if let Ok(port) = clean_port.parse::<u16>() {
-                                println!("Detected port: {}", port);
-                                println!("Emitting sage-desktop-ready event...");
-                                // Emit event to frontend
-                                app_handle.emit_all("sage-desktop-ready", Payload { port }).unwrap();
-                            }
-                        }
-                    }
-                }
-                
-                // Wait for child to exit
-                let status = child.wait().await;
-                println!("Sidecar exited with status: {:?}", status);
-            });
+            tauri::async_runtime::spawn(supervise_backend(app_handle));
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![get_server_port])
+        .invoke_handler(tauri::generate_handler![get_server_port, reveal_in_file_manager])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn path_to_file_uri_escapes_reserved_and_unsafe_characters() {
+        assert_eq!(path_to_file_uri("/home/user/docs"), "file:///home/user/docs");
+        assert_eq!(
+            path_to_file_uri("/home/user/my file #1.txt"),
+            "file:///home/user/my%20file%20%231.txt"
+        );
+        assert_eq!(path_to_file_uri("/tmp/100%"), "file:///tmp/100%25");
+    }
+
+    #[test]
+    fn top_level_imports_splits_comma_separated_names() {
+        let source = "import fastapi, uvicorn\nfrom . import local\nfrom pkg.sub import thing\nimport os.path as p\n";
+        assert_eq!(
+            top_level_imports(source),
+            vec!["fastapi".to_string(), "os".to_string(), "pkg".to_string(), "uvicorn".to_string()]
+        );
+    }
+
+    #[test]
+    fn top_level_imports_ignores_relative_imports() {
+        assert_eq!(top_level_imports("from . import sibling\nfrom .. import parent\n"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn parse_python_log_level_maps_known_prefixes() {
+        assert_eq!(parse_python_log_level("ERROR: boom"), "error");
+        assert_eq!(parse_python_log_level("CRITICAL: boom"), "error");
+        assert_eq!(parse_python_log_level("WARNING: heads up"), "warn");
+        assert_eq!(parse_python_log_level("DEBUG: details"), "debug");
+        assert_eq!(parse_python_log_level("just some stray output"), "info");
+    }
+}